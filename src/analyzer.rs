@@ -4,6 +4,7 @@ use crate::cfg_gen::{
     dasm::{self, InstructionBlock},
     trace::{self, CallEdge, TraceStep},
 };
+use crate::resolver::Resolver;
 use eyre::{eyre, Result};
 use ethers::types::{H160, Bytes};
 use fnv::FnvBuildHasher;
@@ -26,6 +27,8 @@ pub struct ContractCFG {
     pub executed_pcs: HashSet<u16>,
     // 新增：用于存储边的编号
     pub edge_numbering: HashMap<((u16, u16), (u16, u16), Edges), u32>,
+    /// Total gas spent executing each basic block, keyed by its `(start_pc, end_pc)` range.
+    pub block_gas: HashMap<(u16, u16), u64>,
 }
 
 /// Node in the global transaction graph
@@ -36,6 +39,11 @@ pub struct TransactionNode {
     pub instruction: String,
     pub contains_sstore: bool,  // Marks whether it contains SSTORE opcode
     pub contains_add_or_sub: bool, // Marks whether it contains ADD or SUB opcodes
+    pub label: Option<String>, // Resolved human-readable label for the contract, if known
+    /// Contract whose storage an SSTORE in this block actually mutates. Equal
+    /// to `contract_address` unless the block executed under a DELEGATECALL
+    /// (or CALLCODE) context, in which case it's the delegating contract.
+    pub storage_owner: H160,
 }
 
 impl Default for TransactionNode {
@@ -45,7 +53,9 @@ impl Default for TransactionNode {
             pc: 0,
             instruction: String::new(),
             contains_sstore: false,
+            storage_owner: H160::zero(),
             contains_add_or_sub: false,
+            label: None,
         }
     }
 }
@@ -53,8 +63,62 @@ impl Default for TransactionNode {
 /// Edge in the global transaction graph
 #[derive(Clone, Debug)]
 pub enum TransactionEdge {
-    Internal(String),    // Internal contract flow, string represents edge type
-    External(String),    // Cross-contract call, string represents call type (CALL, DELEGATECALL, etc.)
+    Internal(String), // Internal contract flow, string represents edge type
+    External {
+        call_type: String,              // CALL, DELEGATECALL, STATICCALL, etc.
+        function_signature: Option<String>, // Resolved signature of the callee's selector, if known
+    },
+    Return(String), // Control returning to the caller, string represents the call type that is unwinding
+}
+
+/// Controls which call frames `TransactionAnalyzer::format_call_trace` prints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShowCalls {
+    /// Print no call frames at all.
+    None,
+    /// Print only calls into contracts seen in `contract_addresses`.
+    User,
+    /// Print everything except the precompiles at 0x1..=0x9; no other system
+    /// addresses are hidden.
+    System,
+    /// Print every call frame, including precompiles.
+    All,
+}
+
+/// Well-known precompile address range (0x1..=0x9), hidden under `ShowCalls::System`.
+fn is_precompile(address: &H160) -> bool {
+    let bytes = address.as_bytes();
+    bytes[..19].iter().all(|b| *b == 0) && (1..=9).contains(&bytes[19])
+}
+
+/// Sum each executed step's gas cost into the basic block its `pc` falls in.
+fn accumulate_block_gas(steps: &[TraceStep], blocks: &[InstructionBlock]) -> HashMap<(u16, u16), u64> {
+    let mut block_gas: HashMap<(u16, u16), u64> = HashMap::new();
+
+    for step in steps {
+        let (Some(pc), Some(gas_cost)) = (step.pc, step.gas_cost) else {
+            continue;
+        };
+        if let Some(block) = blocks.iter().find(|b| b.start_pc <= pc && pc <= b.end_pc) {
+            *block_gas.entry((block.start_pc, block.end_pc)).or_insert(0) += gas_cost;
+        }
+    }
+
+    block_gas
+}
+
+/// Map a fraction in `[0, 1]` (a block's gas relative to the hottest block's)
+/// onto a green -> yellow -> red gradient, for use as a DOT `fillcolor`.
+fn gas_heat_color(fraction: f64) -> String {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let (r, g) = if fraction < 0.5 {
+        // green -> yellow
+        (fraction * 2.0, 1.0)
+    } else {
+        // yellow -> red
+        (1.0, 1.0 - (fraction - 0.5) * 2.0)
+    };
+    format!("#{:02x}{:02x}00", (r * 255.0) as u8, (g * 255.0) as u8)
 }
 
 pub struct TransactionAnalyzer {
@@ -65,13 +129,14 @@ pub struct TransactionAnalyzer {
     pub call_edges: Vec<CallEdge>,
     pub global_graph: DiGraph<TransactionNode, TransactionEdge>,
     pub node_mapping: HashMap<(H160, u16), petgraph::graph::NodeIndex>,
+    pub resolver: Resolver,
 }
 
 impl TransactionAnalyzer {
     pub fn new(trace_steps: Vec<TraceStep>) -> Self {
         let contract_addresses = trace::extract_contract_addresses(&trace_steps);
         let call_edges = trace::extract_call_edges(&trace_steps);
-        
+
         Self {
             trace_steps,
             contract_addresses,
@@ -80,8 +145,15 @@ impl TransactionAnalyzer {
             call_edges,
             global_graph: DiGraph::new(),
             node_mapping: HashMap::new(),
+            resolver: Resolver::new().load_signature_cache(crate::resolver::DEFAULT_SIGNATURE_CACHE_PATH),
         }
     }
+
+    /// Replace the default resolver (e.g. to load a custom signature cache
+    /// or register known contract labels before building the global graph).
+    pub fn set_resolver(&mut self, resolver: Resolver) {
+        self.resolver = resolver;
+    }
     
     pub fn from_trace_file(trace_path: &str) -> Result<Self> {
         let trace_steps = trace::parse_trace_file(trace_path)?;
@@ -170,11 +242,15 @@ impl TransactionAnalyzer {
         // 假设这里调用 process_trace_and_number_edges
         let edge_numbering = self.process_trace_and_number_edges(&mut cfg_runner, &filtered_steps);
 
+        // Attribute each executed step's gas cost to the basic block it falls in.
+        let block_gas = accumulate_block_gas(&filtered_steps, &instruction_blocks);
+
         Ok(ContractCFG {
             address: *address,
             cfg_runner: cfg_runner,
             executed_pcs,
             edge_numbering,
+            block_gas,
         })
     }
     // 新增：处理trace并编号边的函数
@@ -282,6 +358,11 @@ impl TransactionAnalyzer {
     
     /// Create global transaction graph
     pub fn build_global_transaction_graph(&mut self) -> Result<()> {
+        // Resolve, for each executed block, which contract's storage its
+        // SSTOREs actually write to (its own contract's, unless that contract
+        // was entered via DELEGATECALL/CALLCODE).
+        let storage_owners = self.compute_storage_owners();
+
         // Create global graph nodes for each node in contract CFGs
         for (address, contract_cfg) in &self.contract_cfgs {
             for node in contract_cfg.cfg_runner.cfg_dag.nodes() {
@@ -306,6 +387,8 @@ impl TransactionAnalyzer {
                         instruction: instruction_block.to_string(),
                         contains_sstore, // Set SSTORE flag
                         contains_add_or_sub, // Set ADD/SUB flag
+                        label: self.resolver.resolve_label(address),
+                        storage_owner: storage_owners.get(&(*address, pc)).copied().unwrap_or(*address),
                     };
                     
                     // Add to global graph
@@ -340,27 +423,247 @@ impl TransactionAnalyzer {
             }
         }
         
-        // Add cross-contract call edges
-        for edge in &self.call_edges {
-            if let (Some(from_idx), Some(to_idx)) = (
-                self.node_mapping.get(&(edge.from_addr, edge.from_pc)),
-                // Assume target contract's entry PC is 0
-                self.node_mapping.get(&(edge.to_addr, 0))
-            ) {
-                // Add external call edge
-                self.global_graph.add_edge(
-                    *from_idx,
-                    *to_idx,
-                    TransactionEdge::External(edge.call_type.clone()),
-                );
+        // Add cross-contract call and return edges, reconstructed from the
+        // trace's depth field rather than assuming the callee's entry is PC 0.
+        self.build_call_return_edges();
+
+        Ok(())
+    }
+
+    /// Walk `trace_steps` and add `External`/`Return` edges to the global
+    /// graph based on the trace's `depth` field, rather than assuming every
+    /// call lands on the callee's PC 0.
+    ///
+    /// An explicit call stack of caller frames (address and call type) is
+    /// kept while scanning, and each `Return` edge carries the call type of
+    /// the frame it unwinds:
+    /// when depth increases we've entered a callee, so we link the caller's
+    /// current block to the callee's actual first executed block and push
+    /// the caller's frame; when depth decreases we've returned, so we link
+    /// the last executed block of the inner frame to the block the caller
+    /// resumes in -- the successor of the call site, which is just the
+    /// current step once depth has dropped back down. This keeps nested and
+    /// repeated calls to the same contract linked to the correct frames.
+    fn build_call_return_edges(&mut self) {
+        struct Frame {
+            address: H160,
+            call_type: String,
+        }
+
+        let mut call_stack: Vec<Frame> = Vec::new();
+        let mut prev: Option<(H160, u16, u16, u64)> = None; // (address, raw_pc, block_pc, depth)
+
+        for step in &self.trace_steps {
+            let (Some(address), Some(pc), Some(depth)) = (step.address, step.pc, step.depth) else {
+                continue;
+            };
+            let Some(contract_cfg) = self.contract_cfgs.get(&address) else {
+                continue;
+            };
+            let block_pc = contract_cfg.cfg_runner.get_node_from_pc(pc).0;
+
+            if let Some((prev_addr, prev_pc, prev_block_pc, prev_depth)) = prev {
+                if depth > prev_depth {
+                    // Entered a callee: link the caller's block to the callee's actual entry block.
+                    let call_edge = self.find_call_edge(prev_addr, prev_pc, address);
+                    let call_type = call_edge.map(|e| e.call_type.clone()).unwrap_or_else(|| "CALL".to_string());
+                    if let (Some(&from_idx), Some(&to_idx)) = (
+                        self.node_mapping.get(&(prev_addr, prev_block_pc)),
+                        self.node_mapping.get(&(address, block_pc)),
+                    ) {
+                        let function_signature = call_edge
+                            .and_then(|e| Resolver::extract_call_selector(&self.trace_steps, e))
+                            .and_then(|selector| self.resolver.resolve_selector(&selector));
+                        self.global_graph.add_edge(
+                            from_idx,
+                            to_idx,
+                            TransactionEdge::External { call_type: call_type.clone(), function_signature },
+                        );
+                    }
+                    call_stack.push(Frame { address: prev_addr, call_type });
+                } else if depth < prev_depth {
+                    // Returned: link the inner frame's last executed block to
+                    // the block the caller resumes in -- the successor of
+                    // the call site, not the call site itself. `address`/
+                    // `block_pc` at this point are the current step's, i.e.
+                    // where the caller actually continued executing.
+                    if let Some(frame) = call_stack.pop() {
+                        if let (Some(&from_idx), Some(&to_idx)) = (
+                            self.node_mapping.get(&(prev_addr, prev_block_pc)),
+                            self.node_mapping.get(&(frame.address, block_pc)),
+                        ) {
+                            self.global_graph.add_edge(from_idx, to_idx, TransactionEdge::Return(frame.call_type));
+                        }
+                    }
+                }
             }
+
+            prev = Some((address, pc, block_pc, depth));
         }
-        
-        Ok(())
     }
-    
+
+    /// Find the `CallEdge` that corresponds to a call made from `(from_addr, from_pc)` into `to_addr`.
+    fn find_call_edge(&self, from_addr: H160, from_pc: u16, to_addr: H160) -> Option<&CallEdge> {
+        self.call_edges
+            .iter()
+            .find(|edge| edge.from_addr == from_addr && edge.from_pc == from_pc && edge.to_addr == to_addr)
+    }
+
+    /// Walk `trace_steps` once to determine, for each basic block actually
+    /// executed, whose storage its SSTOREs mutate: a contract entered via
+    /// DELEGATECALL/CALLCODE inherits its caller's storage owner, since
+    /// DELEGATECALL runs the callee's code against the caller's storage;
+    /// every other call type starts a fresh storage context owned by the
+    /// callee itself. Ownership is tracked with the same call stack as
+    /// `build_call_return_edges` so each occurrence of a block is attributed
+    /// using the caller active at that point in the trace, rather than a
+    /// single owner cached once per address.
+    ///
+    /// The result is still keyed by `(address, block_pc)`, the same key the
+    /// global graph uses for its one node per executed block, so if the same
+    /// library block is delegatecalled from more than one proxy in the same
+    /// transaction, only the last occurrence's owner survives in this map --
+    /// the node can't represent two owners at once. Disambiguating that would
+    /// need per-call-context nodes, which is out of scope here.
+    fn compute_storage_owners(&self) -> HashMap<(H160, u16), H160> {
+        let mut owners: HashMap<(H160, u16), H160> = HashMap::new();
+        let mut call_stack: Vec<H160> = Vec::new(); // owner to restore on return
+        let mut current_owner: Option<H160> = None;
+        let mut prev: Option<(H160, u16, u64)> = None;
+
+        for step in &self.trace_steps {
+            let (Some(address), Some(pc), Some(depth)) = (step.address, step.pc, step.depth) else {
+                continue;
+            };
+
+            if let Some((prev_addr, prev_pc, prev_depth)) = prev {
+                if depth > prev_depth {
+                    let caller_owner = current_owner.unwrap_or(prev_addr);
+                    let call_edge = self.find_call_edge(prev_addr, prev_pc, address);
+                    let is_delegated = call_edge
+                        .map(|edge| matches!(edge.call_type.as_str(), "DELEGATECALL" | "CALLCODE"))
+                        .unwrap_or(false);
+                    call_stack.push(caller_owner);
+                    current_owner = Some(if is_delegated { caller_owner } else { address });
+                } else if depth < prev_depth {
+                    current_owner = call_stack.pop().or(current_owner);
+                }
+            } else {
+                current_owner = Some(address);
+            }
+
+            let owner = current_owner.unwrap_or(address);
+            let block_pc = self
+                .contract_cfgs
+                .get(&address)
+                .map(|cfg| cfg.cfg_runner.get_node_from_pc(pc).0)
+                .unwrap_or(pc);
+            owners.insert((address, block_pc), owner);
+
+            prev = Some((address, pc, depth));
+        }
+
+        owners
+    }
+
+    /// Render an indented, foundry-style textual call trace reconstructed
+    /// from `trace_steps`, filtered by `level`.
+    ///
+    /// Each line shows the call type, the resolved target (falling back to
+    /// the raw address), and the decoded selector when known. Nested calls
+    /// are indented by the reconstructed call-stack depth (zero at the
+    /// top-level call) so the output reads as a call tree, without requiring
+    /// a `dot` conversion step.
+    ///
+    /// Frames are driven entirely by the step whose `depth` actually
+    /// increased -- not by stepping a parallel iterator over `call_edges` --
+    /// so a depth increase `call_edges` doesn't cover (e.g. `CREATE`) can't
+    /// desynchronize every frame after it.
+    pub fn format_call_trace(&self, level: ShowCalls) -> String {
+        let mut output = String::new();
+        let mut call_stack: Vec<H160> = Vec::new();
+        let mut prev: Option<(H160, u16, u64, Option<String>)> = None; // (address, pc, depth, op)
+
+        for step in &self.trace_steps {
+            let (Some(address), Some(pc), Some(depth)) = (step.address, step.pc, step.depth) else {
+                continue;
+            };
+
+            if let Some((prev_addr, prev_pc, prev_depth, ref prev_op)) = prev {
+                if depth > prev_depth {
+                    let call_edge = self.find_call_edge(prev_addr, prev_pc, address);
+                    let call_type = call_edge
+                        .map(|e| e.call_type.clone())
+                        .or_else(|| prev_op.clone())
+                        .unwrap_or_else(|| "CALL".to_string());
+                    let selector = call_edge
+                        .and_then(|e| Resolver::extract_call_selector(&self.trace_steps, e))
+                        .and_then(|selector| self.resolver.resolve_selector(&selector).or(Some(selector)));
+
+                    self.write_call_frame(&mut output, address, &call_type, selector, call_stack.len() as u64, level);
+                    call_stack.push(prev_addr);
+                } else if depth < prev_depth {
+                    call_stack.pop();
+                }
+            }
+
+            prev = Some((address, pc, depth, step.op.clone()));
+        }
+
+        output
+    }
+
+    fn frame_visible(&self, address: &H160, level: ShowCalls) -> bool {
+        match level {
+            ShowCalls::None => false,
+            ShowCalls::System => !is_precompile(address),
+            ShowCalls::User => self.contract_addresses.contains(address),
+            ShowCalls::All => true,
+        }
+    }
+
+    /// Look up the total gas attributed to the basic block containing `node`.
+    fn node_block_gas(&self, node: &TransactionNode) -> Option<u64> {
+        let contract_cfg = self.contract_cfgs.get(&node.contract_address)?;
+        let block = contract_cfg.cfg_runner.get_node_from_pc(node.pc);
+        contract_cfg.block_gas.get(&block).copied()
+    }
+
+    fn write_call_frame(
+        &self,
+        output: &mut String,
+        target_addr: H160,
+        call_type: &str,
+        selector: Option<String>,
+        depth: u64,
+        level: ShowCalls,
+    ) {
+        if !self.frame_visible(&target_addr, level) {
+            return;
+        }
+
+        let target = self
+            .resolver
+            .resolve_label(&target_addr)
+            .unwrap_or_else(|| format!("{:?}", target_addr));
+
+        let indent = "  ".repeat(depth as usize);
+        match selector {
+            Some(sig) => writeln!(output, "{}[{}] {}::{}", indent, call_type, target, sig).unwrap(),
+            None => writeln!(output, "{}[{}] {}", indent, call_type, target).unwrap(),
+        }
+    }
+
     /// Export global transaction graph in DOT format
     pub fn export_global_graph_dot(&self) -> String {
+        self.export_global_graph_dot_with_options(false)
+    }
+
+    /// Export global transaction graph in DOT format, optionally coloring
+    /// nodes as a gas heat map (green -> yellow -> red by gas relative to the
+    /// hottest block) instead of the fixed SSTORE/ADD-SUB palette. Node labels
+    /// show each block's gas and its percentage of the total executed gas.
+    pub fn export_global_graph_dot_with_options(&self, gas_heatmap: bool) -> String {
         let mut dot_str = String::new();
 
         writeln!(&mut dot_str, "digraph G {{").unwrap();
@@ -369,19 +672,64 @@ impl TransactionAnalyzer {
         writeln!(&mut dot_str, "    edge [color=\"#414868\", fontcolor=\"#c0caf5\", fontname=\"Helvetica\"];").unwrap();
         writeln!(&mut dot_str, "    bgcolor=\"#1a1b26\";").unwrap();
 
+        let total_gas: u64 = if gas_heatmap {
+            self.contract_cfgs.values().map(|cfg| cfg.block_gas.values().sum::<u64>()).sum()
+        } else {
+            0
+        };
+        // Heat color is normalized against the single hottest block, not the
+        // grand total -- a transaction has many blocks, so any one block's
+        // share of the total is almost always small and the map would never
+        // reach red.
+        let max_block_gas: u64 = if gas_heatmap {
+            self.contract_cfgs
+                .values()
+                .flat_map(|cfg| cfg.block_gas.values())
+                .copied()
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
         // Add nodes
         for (idx, node) in self.global_graph.node_indices().zip(self.global_graph.node_weights()) {
-            let addr_str = format!("{:?}", node.contract_address);
-            let label = format!("{}\\nPC: {}\\n{}", addr_str, node.pc, node.instruction.replace('"', "\\\""));
+            let addr_str = node.label.clone().unwrap_or_else(|| format!("{:?}", node.contract_address));
+
+            let gas = gas_heatmap
+                .then(|| self.node_block_gas(node))
+                .flatten();
+
+            let delegated_write = node.contains_sstore && node.storage_owner != node.contract_address;
+
+            let mut label = match gas {
+                Some(gas) if total_gas > 0 => format!(
+                    "{}\\nPC: {}\\n{}\\ngas: {} ({:.1}%)",
+                    addr_str,
+                    node.pc,
+                    node.instruction.replace('"', "\\\""),
+                    gas,
+                    gas as f64 / total_gas as f64 * 100.0
+                ),
+                _ => format!("{}\\nPC: {}\\n{}", addr_str, node.pc, node.instruction.replace('"', "\\\"")),
+            };
+            if delegated_write {
+                write!(&mut label, "\\nwrites storage of {:?}", node.storage_owner).unwrap();
+            }
 
             // Apply the same highlighting logic as in cfg_dot_str_highlighted_only
-            // Color priority: SSTORE > ADD/SUB > others
-            let fillcolor = if node.contains_sstore {
-                "#f7768e" // Pink for SSTORE
+            let fillcolor = if gas_heatmap && max_block_gas > 0 {
+                let fraction = gas.unwrap_or(0) as f64 / max_block_gas as f64;
+                gas_heat_color(fraction)
+            } else if delegated_write {
+                // Color priority: delegated SSTORE > own SSTORE > ADD/SUB > others
+                "#bb9af7".to_string() // Purple for SSTORE that writes another contract's storage
+            } else if node.contains_sstore {
+                "#f7768e".to_string() // Pink for SSTORE
             } else if node.contains_add_or_sub {
-                "#ff9e64" // Orange for ADD/SUB
+                "#ff9e64".to_string() // Orange for ADD/SUB
             } else {
-                "#9ece6a" // Green for others
+                "#9ece6a".to_string() // Green for others
             };
 
             writeln!(
@@ -436,12 +784,24 @@ impl TransactionAnalyzer {
                     };
                     writeln!(&mut dot_str, "    {} -> {} [{}];", from, to, style).unwrap();
                 },
-                TransactionEdge::External(call_type) => {
+                TransactionEdge::External { call_type, function_signature } => {
                     // 外部调用边，为编号（此处是call_type）设置颜色
+                    let edge_text = match function_signature {
+                        Some(sig) => format!("{}: {}", call_type, sig),
+                        None => call_type.clone(),
+                    };
                     let style = format!(
                         "color=\"#7aa2f7\", style=\"bold\", penwidth=2, label=<{}>",
                         // 用蓝色突出显示外部调用类型
-                        format!("<font color=\"#0000ff\">{}</font>", call_type)
+                        format!("<font color=\"#0000ff\">{}</font>", edge_text)
+                    );
+                    writeln!(&mut dot_str, "    {} -> {} [{}];", from, to, style).unwrap();
+                }
+                TransactionEdge::Return(call_type) => {
+                    // 返回边，使用虚线与调用边区分
+                    let style = format!(
+                        "color=\"#7aa2f7\", style=\"dashed\", label=<{}>",
+                        format!("<font color=\"#7aa2f7\">return ({})</font>", call_type)
                     );
                     writeln!(&mut dot_str, "    {} -> {} [{}];", from, to, style).unwrap();
                 }
@@ -459,7 +819,104 @@ impl TransactionAnalyzer {
         std::fs::write(output_path, dot_str)?;
         Ok(())
     }
-    
+
+    /// Export the global transaction graph as JSON, for tooling that wants
+    /// structured data instead of parsing Graphviz DOT text.
+    ///
+    /// Schema:
+    /// ```text
+    /// {
+    ///   "nodes": [
+    ///     { "id": 0, "contract_address": "0x...", "pc": 0, "instruction": "...",
+    ///       "contains_sstore": false, "contains_add_or_sub": false, "label": null,
+    ///       "storage_owner": null }
+    ///   ],
+    ///   "edges": [
+    ///     { "from": 0, "to": 1, "kind": "internal", "edge_type": "Jump", "edge_number": 0 },
+    ///     { "from": 1, "to": 2, "kind": "external", "edge_type": "CALL", "function_signature": "transfer(address,uint256)" },
+    ///     { "from": 2, "to": 1, "kind": "return", "edge_type": "CALL" }
+    ///   ]
+    /// }
+    /// ```
+    pub fn export_global_graph_json(&self) -> String {
+        let nodes: Vec<serde_json::Value> = self
+            .global_graph
+            .node_indices()
+            .zip(self.global_graph.node_weights())
+            .map(|(idx, node)| {
+                serde_json::json!({
+                    "id": idx.index(),
+                    "contract_address": format!("{:?}", node.contract_address),
+                    "pc": node.pc,
+                    "instruction": node.instruction,
+                    "contains_sstore": node.contains_sstore,
+                    "contains_add_or_sub": node.contains_add_or_sub,
+                    "label": node.label,
+                    "storage_owner": node.contains_sstore.then(|| format!("{:?}", node.storage_owner)),
+                })
+            })
+            .collect();
+
+        let edges: Vec<serde_json::Value> = self
+            .global_graph
+            .edge_references()
+            .map(|edge| {
+                let (from, to) = (edge.source().index(), edge.target().index());
+                match edge.weight() {
+                    TransactionEdge::Internal(edge_type) => {
+                        let from_node = self.global_graph.node_weight(edge.source()).unwrap();
+                        let to_node = self.global_graph.node_weight(edge.target()).unwrap();
+                        let edge_number = self.internal_edge_number(from_node, to_node, edge_type);
+                        serde_json::json!({
+                            "from": from,
+                            "to": to,
+                            "kind": "internal",
+                            "edge_type": edge_type,
+                            "edge_number": edge_number,
+                        })
+                    }
+                    TransactionEdge::External { call_type, function_signature } => serde_json::json!({
+                        "from": from,
+                        "to": to,
+                        "kind": "external",
+                        "edge_type": call_type,
+                        "function_signature": function_signature,
+                    }),
+                    TransactionEdge::Return(call_type) => serde_json::json!({
+                        "from": from,
+                        "to": to,
+                        "kind": "return",
+                        "edge_type": call_type,
+                    }),
+                }
+            })
+            .collect();
+
+        serde_json::json!({ "nodes": nodes, "edges": edges }).to_string()
+    }
+
+    /// Save the global transaction graph to a JSON file.
+    pub fn save_global_graph_json(&self, output_path: &str) -> Result<()> {
+        let json_str = self.export_global_graph_json();
+        std::fs::write(output_path, json_str)?;
+        Ok(())
+    }
+
+    /// Look up the stable numbering assigned to an internal edge by `process_trace_and_number_edges`.
+    fn internal_edge_number(&self, from_node: &TransactionNode, to_node: &TransactionNode, edge_type: &str) -> Option<u32> {
+        let contract_cfg = self.contract_cfgs.get(&from_node.contract_address)?;
+        let from_block = contract_cfg.cfg_runner.get_node_from_pc(from_node.pc);
+        let to_block = contract_cfg.cfg_runner.get_node_from_pc(to_node.pc);
+        let edge_type_enum = match edge_type {
+            "ConditionTrue" => Edges::ConditionTrue,
+            "ConditionFalse" => Edges::ConditionFalse,
+            "SymbolicJump" => Edges::SymbolicJump,
+            _ => Edges::Jump,
+        };
+        contract_cfg.edge_numbering.get(&(from_block, to_block, edge_type_enum)).copied()
+    }
+
+
     /// Convert to other formats (PNG, SVG, etc.)
     pub fn convert_to_image(&self, dot_path: &str, output_path: &str) -> Result<()> {
         let ext = Path::new(output_path).extension().and_then(|s| s.to_str()).unwrap_or("png");
@@ -478,14 +935,20 @@ impl TransactionAnalyzer {
         Ok(())
     }
 
-    /// Export individual contract CFGs with only highlighted nodes and edges
+    /// Export individual contract CFGs with only highlighted nodes and edges.
+    ///
+    /// `cfg_dot_str_highlighted_only` doesn't take a gas map, so unlike
+    /// `export_global_graph_dot_with_options` this view has no gas heat-map
+    /// variant -- it always uses the fixed SSTORE/ADD-SUB palette.
     // 在TransactionAnalyzer的export_contract_highlighted_cfgs方法中
     pub fn export_contract_highlighted_cfgs(&self) -> HashMap<H160, String> {
         let mut results = HashMap::new();
 
         for (address, contract_cfg) in &self.contract_cfgs {
             // 传入预定义的边编号
-            let dot_str = contract_cfg.cfg_runner.cfg_dot_str_highlighted_only(&contract_cfg.edge_numbering);
+            let dot_str = contract_cfg
+                .cfg_runner
+                .cfg_dot_str_highlighted_only(&contract_cfg.edge_numbering);
             results.insert(*address, dot_str);
         }
 
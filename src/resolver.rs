@@ -0,0 +1,172 @@
+//! Contract-address and function-selector resolution.
+//!
+//! This module turns the raw hex addresses and 4-byte selectors seen in a
+//! [`TransactionAnalyzer`](crate::analyzer::TransactionAnalyzer) into
+//! human-readable names: known contract labels, and decoded function
+//! signatures looked up from a selector database. Resolution is best-effort
+//! -- callers get `None` back whenever a selector or address can't be
+//! identified, and are expected to fall back to the raw hex.
+
+use crate::cfg_gen::trace::{CallEdge, TraceStep};
+use ethers::types::H160;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Default location of the offline selector cache, relative to the process's
+/// current working directory.
+pub const DEFAULT_SIGNATURE_CACHE_PATH: &str = "data/selectors.json";
+
+/// Resolves contract addresses and function selectors to human-readable names.
+///
+/// Addresses are resolved from the label map registered with `with_labels`;
+/// there are no built-in labels, so it is empty by default. Selectors are
+/// resolved from an offline JSON cache keyed by the 4-byte hex selector
+/// (e.g. `"0xa9059cbb"` -> `"transfer(address,uint256)"`). Enabling
+/// `online_lookup` routes cache misses to `lookup_selector_online`, which
+/// makes no network request and returns `None`, so only the offline cache
+/// ever yields a signature.
+pub struct Resolver {
+    signatures: HashMap<String, String>,
+    labels: HashMap<H160, String>,
+    online_lookup: bool,
+}
+
+impl Resolver {
+    /// Build a resolver with no known signatures or labels and online lookup disabled.
+    pub fn new() -> Self {
+        Self {
+            signatures: HashMap::new(),
+            labels: HashMap::new(),
+            online_lookup: false,
+        }
+    }
+
+    /// Load the offline selector cache from `path` (a JSON object of
+    /// `{ "0xselector": "signature(types)" }`). Missing files are treated as
+    /// an empty cache rather than an error, since the resolver is best-effort.
+    pub fn load_signature_cache(mut self, path: impl AsRef<Path>) -> Self {
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            if let Ok(map) = serde_json::from_str::<HashMap<String, String>>(&contents) {
+                self.signatures = map;
+            }
+        }
+        self
+    }
+
+    /// Register known address -> label mappings (e.g. `"Uniswap V2: Router"`).
+    pub fn with_labels(mut self, labels: HashMap<H160, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Route selectors that aren't found in the offline cache to
+    /// `lookup_selector_online`. Disabled by default. That fallback performs
+    /// no network lookup and always returns `None`.
+    pub fn with_online_lookup(mut self, enabled: bool) -> Self {
+        self.online_lookup = enabled;
+        self
+    }
+
+    /// Resolve a known contract address to a human-readable label.
+    pub fn resolve_label(&self, address: &H160) -> Option<String> {
+        self.labels.get(address).cloned()
+    }
+
+    /// Resolve a 4-byte selector (formatted as `"0x########"`) to its signature.
+    pub fn resolve_selector(&self, selector: &str) -> Option<String> {
+        if let Some(sig) = self.signatures.get(selector) {
+            return Some(sig.clone());
+        }
+        if self.online_lookup {
+            return Self::lookup_selector_online(selector);
+        }
+        None
+    }
+
+    /// Fallback for a selector not present in the offline cache. Only called
+    /// when `online_lookup` is enabled; it makes no network request and
+    /// always returns `None`.
+    fn lookup_selector_online(_selector: &str) -> Option<String> {
+        // Network access is intentionally not performed inside the library;
+        // callers that enable `online_lookup` are expected to pre-populate
+        // the cache (e.g. via a CLI command that hits openchain/4byte and
+        // persists the result to `DEFAULT_SIGNATURE_CACHE_PATH`).
+        None
+    }
+
+    /// Extract the 4-byte selector for a `CALL`/`STATICCALL`/`DELEGATECALL`
+    /// edge by reading `argsOffset`/`argsLength` off the caller's stack and
+    /// the corresponding bytes from that step's memory.
+    ///
+    /// Returns `None` when the edge doesn't correspond to a step with a
+    /// decodable stack/memory (e.g. calldata shorter than 4 bytes).
+    pub fn extract_call_selector(trace_steps: &[TraceStep], call_edge: &CallEdge) -> Option<String> {
+        let step = trace_steps.iter().find(|step| {
+            step.pc == Some(call_edge.from_pc)
+                && step.address == Some(call_edge.from_addr)
+                && matches!(
+                    step.op.as_deref(),
+                    Some("CALL") | Some("CALLCODE") | Some("STATICCALL") | Some("DELEGATECALL")
+                )
+        })?;
+
+        let stack = step.stack.as_ref()?;
+        let len = stack.len();
+
+        // CALL/CALLCODE push `gas,addr,value,argsOffset,argsLength,...`;
+        // STATICCALL/DELEGATECALL have no `value` slot.
+        let has_value = matches!(step.op.as_deref(), Some("CALL") | Some("CALLCODE"));
+        let (args_offset_idx, args_length_idx) = if has_value {
+            (len.checked_sub(4)?, len.checked_sub(5)?)
+        } else {
+            (len.checked_sub(3)?, len.checked_sub(4)?)
+        };
+
+        let args_offset = parse_stack_u64(stack.get(args_offset_idx)?)?;
+        let args_length = parse_stack_u64(stack.get(args_length_idx)?)?;
+        if args_length < 4 {
+            return None;
+        }
+
+        let memory = step.memory.as_ref()?;
+        let calldata = flatten_memory(memory);
+        let start = args_offset as usize;
+        let bytes = calldata.get(start..start.checked_add(4)?)?;
+
+        Some(format!("0x{}", hex::encode(bytes)))
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a `"0x..."` stack value into a `u64`, saturating on overflow (stack
+/// values are 256-bit, but offsets/lengths realistically fit in a `u64`).
+/// Returns `None` only when `value` isn't valid hex at all.
+fn parse_stack_u64(value: &str) -> Option<u64> {
+    let trimmed = value.strip_prefix("0x").unwrap_or(value);
+    match u64::from_str_radix(trimmed, 16) {
+        Ok(parsed) => Some(parsed),
+        Err(_) if !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_hexdigit()) => {
+            // Valid hex, just wider than a u64 -- saturate rather than bail,
+            // since a real offset/length that size is already unusable.
+            Some(u64::MAX)
+        }
+        Err(_) => None,
+    }
+}
+
+/// Flatten the trace's per-word memory dump into a contiguous byte vector.
+fn flatten_memory(words: &[String]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(words.len() * 32);
+    for word in words {
+        let trimmed = word.strip_prefix("0x").unwrap_or(word);
+        if let Ok(decoded) = hex::decode(trimmed) {
+            bytes.extend(decoded);
+        }
+    }
+    bytes
+}